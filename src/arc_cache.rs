@@ -0,0 +1,177 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// http://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+//! An Adaptive Replacement Cache (Megiddo & Modha, 2003), used as an alternative to plain LRU
+//! eviction for keys whose access pattern is skewed rather than uniformly recent-biased.
+//!
+//! Two resident lists are tracked: `t1` holds keys seen once recently, `t2` holds keys seen at
+//! least twice (i.e. "hot" keys). Two further ghost lists, `b1` and `b2`, remember only the keys
+//! (not the values) of entries recently evicted from `t1` and `t2` respectively. A hit against a
+//! ghost list is what lets the cache adapt: a hit in `b1` means `t1` is being evicted too
+//! aggressively, so the target size `p` for `t1` grows (favouring recency); a hit in `b2` means
+//! the opposite, so `p` shrinks (favouring frequency).
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+pub struct ArcCache<Key, Value> {
+    capacity: usize,
+    // Target size for `t1`; adapts on every ghost-list hit.
+    p: usize,
+    t1: VecDeque<Key>,
+    t2: VecDeque<Key>,
+    b1: VecDeque<Key>,
+    b2: VecDeque<Key>,
+    values: HashMap<Key, Value>,
+}
+
+impl<Key: Clone + Eq + Hash, Value> ArcCache<Key, Value> {
+    pub fn with_capacity(capacity: usize) -> ArcCache<Key, Value> {
+        ArcCache {
+            capacity: capacity,
+            p: 0,
+            t1: VecDeque::new(),
+            t2: VecDeque::new(),
+            b1: VecDeque::new(),
+            b2: VecDeque::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn peek(&self, key: &Key) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Iterates over every resident (non-ghost) key-value pair, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Value)> {
+        self.t1.iter().chain(self.t2.iter()).filter_map(move |key| {
+            self.values.get(key).map(|value| (key, value))
+        })
+    }
+
+    /// Looks up `key` without inserting, promoting it from `t1` to `t2` on a hit (the entry has
+    /// now been seen at least twice, so it counts as "hot"), or refreshing its MRU position
+    /// within `t2` if it's already there. Without the latter, a key accessed repeatedly via
+    /// `get_mut` alone (rather than re-inserted via `entry_or_insert_with`) would drift towards
+    /// the LRU end of `t2` and lose the eviction protection this cache is meant to provide.
+    pub fn get_mut(&mut self, key: &Key) -> Option<&mut Value> {
+        if contains(&self.t1, key) {
+            promote(&mut self.t1, &mut self.t2, key);
+        } else if contains(&self.t2, key) {
+            move_to_mru(&mut self.t2, key);
+        }
+        self.values.get_mut(key)
+    }
+
+    pub fn remove(&mut self, key: &Key) -> Option<Value> {
+        remove_from(&mut self.t1, key);
+        remove_from(&mut self.t2, key);
+        remove_from(&mut self.b1, key);
+        remove_from(&mut self.b2, key);
+        self.values.remove(key)
+    }
+
+    /// Returns the existing value for `key`, inserting `default()` first if absent. Runs the
+    /// full ARC access path: resident hits promote to `t2`, ghost hits adapt `p` and evict
+    /// accordingly, and misses evict from whichever of `t1`/`t2` exceeds its target size before
+    /// the new key is admitted into `t1`.
+    pub fn entry_or_insert_with<Default: FnOnce() -> Value>(
+        &mut self,
+        key: Key,
+        default: Default,
+    ) -> &mut Value {
+        if self.values.contains_key(&key) {
+            if contains(&self.t1, &key) {
+                promote(&mut self.t1, &mut self.t2, &key);
+            } else {
+                move_to_mru(&mut self.t2, &key);
+            }
+            return self.values.get_mut(&key).unwrap_or_else(
+                || unreachable!("just confirmed key is present"),
+            );
+        }
+
+        if contains(&self.b1, &key) {
+            let increment = (self.b2.len() / self.b1.len().max(1)).max(1);
+            self.p = (self.p + increment).min(self.capacity);
+            self.replace(false);
+            remove_from(&mut self.b1, &key);
+            self.t2.push_back(key.clone());
+        } else if contains(&self.b2, &key) {
+            let decrement = (self.b1.len() / self.b2.len().max(1)).max(1);
+            self.p = self.p.saturating_sub(decrement);
+            self.replace(true);
+            remove_from(&mut self.b2, &key);
+            self.t2.push_back(key.clone());
+        } else {
+            let t1_and_b1 = self.t1.len() + self.b1.len();
+            if t1_and_b1 == self.capacity {
+                if self.t1.len() < self.capacity {
+                    let _ = self.b1.pop_front();
+                    self.replace(false);
+                } else if let Some(evicted) = self.t1.pop_front() {
+                    let _ = self.values.remove(&evicted);
+                }
+            } else if t1_and_b1 < self.capacity &&
+                       t1_and_b1 + self.t2.len() + self.b2.len() >= self.capacity
+            {
+                if t1_and_b1 + self.t2.len() + self.b2.len() >= 2 * self.capacity {
+                    let _ = self.b2.pop_front();
+                }
+                self.replace(false);
+            }
+            self.t1.push_back(key.clone());
+        }
+
+        let _ = self.values.insert(key.clone(), default());
+        self.values.get_mut(&key).unwrap_or_else(
+            || unreachable!("just inserted this key"),
+        )
+    }
+
+    // Evicts the LRU end of `t1` or `t2` into the corresponding ghost list, favouring `t1` once
+    // it exceeds the adaptive target size `p` (or once it reaches `p` on a `b2` ghost hit, per the
+    // original ARC replacement rule).
+    fn replace(&mut self, favor_t1: bool) {
+        let t1_len = self.t1.len();
+        if t1_len > 0 && (t1_len > self.p || (favor_t1 && t1_len == self.p)) {
+            if let Some(evicted) = self.t1.pop_front() {
+                let _ = self.values.remove(&evicted);
+                self.b1.push_back(evicted);
+            }
+        } else if let Some(evicted) = self.t2.pop_front() {
+            let _ = self.values.remove(&evicted);
+            self.b2.push_back(evicted);
+        }
+    }
+}
+
+fn contains<Key: PartialEq>(list: &VecDeque<Key>, key: &Key) -> bool {
+    list.iter().any(|candidate| candidate == key)
+}
+
+fn remove_from<Key: PartialEq>(list: &mut VecDeque<Key>, key: &Key) {
+    if let Some(position) = list.iter().position(|candidate| candidate == key) {
+        let _ = list.remove(position);
+    }
+}
+
+fn move_to_mru<Key: PartialEq + Clone>(list: &mut VecDeque<Key>, key: &Key) {
+    remove_from(list, key);
+    list.push_back(key.clone());
+}
+
+fn promote<Key: PartialEq + Clone>(from: &mut VecDeque<Key>, to: &mut VecDeque<Key>, key: &Key) {
+    remove_from(from, key);
+    to.push_back(key.clone());
+}
@@ -40,31 +40,163 @@ extern crate rand;
 // MaidSafe crates
 extern crate lru_time_cache;
 
+mod arc_cache;
+
+use arc_cache::ArcCache;
 use lru_time_cache::LruCache;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+// The two eviction policies a cache within an `Accumulator` may be backed by. Both expose the
+// same narrow surface the `Accumulator` needs, so callers only choose between them at
+// construction time via `with_capacity`/`with_duration` vs `with_arc_capacity`.
+enum Backing<Key, Value> {
+    Lru(LruCache<Key, Value>),
+    Arc(ArcCache<Key, Value>),
+}
+
+impl<Key: PartialOrd + Ord + Clone + Eq + Hash, Value> Backing<Key, Value> {
+    fn len(&self) -> usize {
+        match *self {
+            Backing::Lru(ref cache) => cache.len(),
+            Backing::Arc(ref cache) => cache.len(),
+        }
+    }
+
+    fn peek(&self, key: &Key) -> Option<&Value> {
+        match *self {
+            Backing::Lru(ref cache) => cache.peek(key),
+            Backing::Arc(ref cache) => cache.peek(key),
+        }
+    }
+
+    fn remove(&mut self, key: &Key) -> Option<Value> {
+        match *self {
+            Backing::Lru(ref mut cache) => cache.remove(key),
+            Backing::Arc(ref mut cache) => cache.remove(key),
+        }
+    }
+
+    fn entry_or_insert_with<Default: FnOnce() -> Value>(&mut self, key: Key, default: Default) -> &mut Value {
+        match *self {
+            Backing::Lru(ref mut cache) => cache.entry(key).or_insert_with(default),
+            Backing::Arc(ref mut cache) => cache.entry_or_insert_with(key, default),
+        }
+    }
+
+    // Removes and returns every key-value pair for which `predicate` holds.
+    fn drain_where<Predicate: Fn(&Value) -> bool>(&mut self, predicate: Predicate) -> Vec<(Key, Value)> {
+        let matching_keys: Vec<Key> = match *self {
+            // `LruCache::iter` takes `&mut self` (it prunes expired entries first), so this arm
+            // must borrow mutably even though the other doesn't.
+            Backing::Lru(ref mut cache) => {
+                cache
+                    .iter()
+                    .filter(|&(_, value)| predicate(value))
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            }
+            Backing::Arc(ref cache) => {
+                cache
+                    .iter()
+                    .filter(|&(_, value)| predicate(value))
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            }
+        };
+        matching_keys
+            .into_iter()
+            .filter_map(|key| {
+                let value = self.remove(&key);
+                value.map(|value| (key, value))
+            })
+            .collect()
+    }
+}
+
+// Tracks the values accumulated for a single key plus each value's optional expiry.
+// Pruning happens only when a new value is written (`insert`), not on every read, so `get()` and
+// `is_quorum_reached()` stay allocation-free, `&self` reads of `values`, which can still hold
+// expired values until the next write.
+struct TimedEntry<Value: Eq + Hash> {
+    values: HashSet<Value>,
+    deadlines: HashMap<Value, Instant>,
+}
+
+impl<Value: Clone + Eq + Hash> TimedEntry<Value> {
+    fn new() -> TimedEntry<Value> {
+        TimedEntry {
+            values: HashSet::new(),
+            deadlines: HashMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    // Drops values whose deadline has passed, then records `value` (tracking `deadline`, if any).
+    fn insert(&mut self, value: Value, deadline: Option<Instant>) {
+        self.prune();
+        match deadline {
+            Some(deadline) => {
+                let _ = self.deadlines.insert(value.clone(), deadline);
+            }
+            None => {
+                let _ = self.deadlines.remove(&value);
+            }
+        }
+        let _ = self.values.insert(value);
+    }
+
+    fn prune(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<Value> = self.deadlines
+            .iter()
+            .filter(|&(_, deadline)| *deadline < now)
+            .map(|(value, _)| value.clone())
+            .collect();
+        for value in expired {
+            let _ = self.deadlines.remove(&value);
+            let _ = self.values.remove(&value);
+        }
+    }
+}
 
 /// A key-value store limited by size or time, allowing accumulation of multiple values under a
 /// single key.
 pub struct Accumulator<Key, Value>
 where
     Key: PartialOrd + Ord + Clone,
-    Value: Clone,
+    Value: Clone + Eq + Hash,
 {
     // Expected threshold for resolve
     quorum: usize,
-    lru_cache: LruCache<Key, HashSet<Value>>,
+    // Each accumulated value is paired with an optional deadline; pruned lazily at write time.
+    lru_cache: Backing<Key, TimedEntry<Value>>,
+    // Per-value weights added via `add_weighted`; the largest weight seen for a given value wins.
+    // A distinct, equally-sized store from `lru_cache`: using both `add()` and `add_weighted()` on
+    // the same `Accumulator` can retain up to 2x `capacity` distinct keys in total, and
+    // `cache_size()` only reports the `add()`/`add_with_expiry()` store.
+    weighted_cache: Backing<Key, HashMap<Value, usize>>,
 }
 
-impl<Key: PartialOrd + Ord + Clone, Value: Clone + Eq + Hash> Accumulator<Key, Value> {
-    /// Constructor for capacity based `Accumulator`.
+impl<Key, Value> Accumulator<Key, Value>
+where
+    Key: PartialOrd + Ord + Clone + Hash,
+    Value: Clone + Eq + Hash,
+{
+    /// Constructor for capacity based `Accumulator`, backed by plain LRU eviction.
     ///
     /// `quorum` defines the count at and above which [`add()`](#method.add) will return `Some()`.
+    /// `capacity` bounds the number of keys held by each internal store; see the note on
+    /// [`cache_size()`](#method.cache_size) if using `add_weighted()` alongside `add()`.
     pub fn with_capacity(quorum: usize, capacity: usize) -> Accumulator<Key, Value> {
         Accumulator {
             quorum: quorum,
-            lru_cache: LruCache::with_capacity(capacity),
+            lru_cache: Backing::Lru(LruCache::with_capacity(capacity)),
+            weighted_cache: Backing::Lru(LruCache::with_capacity(capacity)),
         }
     }
 
@@ -74,7 +206,24 @@ impl<Key: PartialOrd + Ord + Clone, Value: Clone + Eq + Hash> Accumulator<Key, V
     pub fn with_duration(quorum: usize, duration: Duration) -> Accumulator<Key, Value> {
         Accumulator {
             quorum: quorum,
-            lru_cache: LruCache::with_expiry_duration(duration),
+            lru_cache: Backing::Lru(LruCache::with_expiry_duration(duration)),
+            weighted_cache: Backing::Lru(LruCache::with_expiry_duration(duration)),
+        }
+    }
+
+    /// Constructor for an `Accumulator` backed by an Adaptive Replacement Cache instead of plain
+    /// LRU eviction.
+    ///
+    /// ARC tracks both recency and frequency of access, so a key that is accumulating votes in
+    /// bursts stays resident through a run of one-off noise keys that plain LRU would otherwise
+    /// have evicted it for. `quorum` defines the count at and above which
+    /// [`add()`](#method.add) will return `Some()`; `capacity` bounds the number of keys held by
+    /// each internal store.
+    pub fn with_arc_capacity(quorum: usize, capacity: usize) -> Accumulator<Key, Value> {
+        Accumulator {
+            quorum: quorum,
+            lru_cache: Backing::Arc(ArcCache::with_capacity(capacity)),
+            weighted_cache: Backing::Arc(ArcCache::with_capacity(capacity)),
         }
     }
 
@@ -83,8 +232,23 @@ impl<Key: PartialOrd + Ord + Clone, Value: Clone + Eq + Hash> Accumulator<Key, V
         self.lru_cache.peek(key).is_some()
     }
 
-    /// Returns whether `key` exists and has accumulated `quorum` or more corresponding values.
+    /// Returns whether `key` exists and holds `quorum` or more accumulated values, counting
+    /// expired values that no write has pruned yet.
+    ///
+    /// Always returns `false` while `quorum` is disabled (see
+    /// [`disable_quorum()`](#method.disable_quorum)).
+    ///
+    /// Expiry (see [`add_with_expiry()`](#method.add_with_expiry)) is only
+    /// checked when `key` is next written to via `add()`/`add_with_expiry()`, not on this read.
+    /// A value whose TTL has elapsed therefore keeps counting towards `quorum` here — and keeps
+    /// being returned by [`get()`](#method.get) — for an unbounded time if `key` is never
+    /// written to again. This trades liveness for a `&self`, allocation-free read; callers that
+    /// need eagerly-pruned reads should call `add`/`add_with_expiry` for `key` (even with an
+    /// already-known value) to force a prune before reading.
     pub fn is_quorum_reached(&self, key: &Key) -> bool {
+        if self.quorum == usize::MAX {
+            return false;
+        }
         match self.lru_cache.peek(key) {
             None => false,
             Some(entry) => entry.len() >= self.quorum,
@@ -93,33 +257,245 @@ impl<Key: PartialOrd + Ord + Clone, Value: Clone + Eq + Hash> Accumulator<Key, V
 
     /// Adds a key-value pair.
     ///
-    /// Returns the corresponding values for `key` if `quorum` or more values have been accumulated,
-    /// otherwise returns `None`.
+    /// Returns the corresponding values for `key` if `quorum` or more values have been
+    /// accumulated, otherwise returns `None`.
     pub fn add(&mut self, key: Key, value: Value) -> Option<&HashSet<Value>> {
-        let entry = self.lru_cache.entry(key).or_insert_with(HashSet::new);
-        let _ = entry.insert(value);
-        if entry.len() >= self.quorum {
-            Some(entry)
+        self.insert(key, value, None)
+    }
+
+    /// Adds a key-value pair whose vote expires independently of the rest of the entry.
+    ///
+    /// Once `ttl` has elapsed since this call, `value` is pruned (along with any other expired
+    /// values under `key`) the next time `key` is written to via `add()`/`add_with_expiry()` —
+    /// see the limitation noted on [`is_quorum_reached()`](#method.is_quorum_reached) for what
+    /// this means for reads in between. Returns the corresponding (live as of this call) values
+    /// for `key` if `quorum` or more have been accumulated, otherwise returns `None`.
+    pub fn add_with_expiry(&mut self, key: Key, value: Value, ttl: Duration) -> Option<&HashSet<Value>> {
+        self.insert(key, value, Some(Instant::now() + ttl))
+    }
+
+    fn insert(&mut self, key: Key, value: Value, deadline: Option<Instant>) -> Option<&HashSet<Value>> {
+        let entry = self.lru_cache.entry_or_insert_with(key, TimedEntry::new);
+        entry.insert(value, deadline);
+        if self.quorum != usize::MAX && entry.len() >= self.quorum {
+            Some(&entry.values)
         } else {
             None
         }
     }
 
+    /// Adds a key-value pair, weighting its vote by `weight` rather than counting it as one.
+    ///
+    /// `quorum` is then a threshold on the summed weight of all distinct values accumulated under
+    /// `key`, rather than on their count; this lets higher-trust sources contribute more towards
+    /// reaching it. A repeated `add_weighted` for the same `value` keeps the larger of the old
+    /// and new weights. Returns the corresponding values for `key` if the summed weight has
+    /// reached `quorum`, otherwise returns `None`.
+    pub fn add_weighted(&mut self, key: Key, value: Value, weight: usize) -> Option<HashSet<Value>> {
+        let entry = self.weighted_cache.entry_or_insert_with(key, HashMap::new);
+        let current_weight = entry.entry(value).or_insert(0);
+        if weight > *current_weight {
+            *current_weight = weight;
+        }
+        let total_weight: usize = entry.values().sum();
+        if self.quorum != usize::MAX && total_weight >= self.quorum {
+            Some(entry.keys().cloned().collect())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the summed weight accumulated under `key` via
+    /// [`add_weighted()`](#method.add_weighted), or `0` if `key` doesn't exist.
+    pub fn accumulated_weight(&self, key: &Key) -> usize {
+        match self.weighted_cache.peek(key) {
+            None => 0,
+            Some(entry) => entry.values().sum(),
+        }
+    }
+
     /// Returns the values accumulated under `key`, or `None` if `key` doesn't exist.
+    ///
+    /// Values added via [`add_with_expiry()`](#method.add_with_expiry) whose TTL has elapsed are
+    /// excluded as of the most recent `add()`/`add_with_expiry()` call for `key` — see the
+    /// limitation noted on [`is_quorum_reached()`](#method.is_quorum_reached): expiry is checked
+    /// on write, not on every `get()`, so a value can be returned here for an unbounded time past
+    /// its TTL if `key` isn't written to again.
     pub fn get(&self, key: &Key) -> Option<&HashSet<Value>> {
-        self.lru_cache.peek(key)
+        self.lru_cache.peek(key).map(|entry| &entry.values)
     }
 
-    /// Removes `key` and all corresponding accumulated values.
+    /// Removes `key` and all corresponding accumulated values, including any weighted votes
+    /// added via [`add_weighted()`](#method.add_weighted).
     pub fn delete(&mut self, key: &Key) {
         let _ = self.lru_cache.remove(key);
+        let _ = self.weighted_cache.remove(key);
     }
 
-    /// Returns the size of the accumulator, i.e. the number of keys held.
+    /// Returns the size of the accumulator, i.e. the number of keys held in the
+    /// `add()`/`add_with_expiry()` store.
+    ///
+    /// Does not include keys that only exist in the separate store backing
+    /// [`add_weighted()`](#method.add_weighted).
     pub fn cache_size(&mut self) -> usize {
         self.lru_cache.len()
     }
 
+    /// Removes every key whose accumulated value count is at or above `quorum` and returns them
+    /// together with their accumulated values. As with
+    /// [`is_quorum_reached()`](#method.is_quorum_reached), expired values that no write has
+    /// pruned yet still count, and are returned.
+    ///
+    /// Useful for event-loop style consumers that can't rely on catching the specific
+    /// [`add()`](#method.add)/[`add_with_expiry()`](#method.add_with_expiry) call that crossed
+    /// the threshold (e.g. because the resolving add happened on another code path) and instead
+    /// periodically harvest everything that has resolved so far. Always returns an empty `Vec`
+    /// while `quorum` is disabled (see [`disable_quorum()`](#method.disable_quorum)).
+    ///
+    /// Only considers the `add()`/`add_with_expiry()` store: keys resolved purely via
+    /// [`add_weighted()`](#method.add_weighted) are not covered (query
+    /// [`accumulated_weight()`](#method.accumulated_weight) for those instead), nor is
+    /// [`SourceAccumulator`](struct.SourceAccumulator.html), which is a separate type.
+    pub fn drain_resolved(&mut self) -> Vec<(Key, HashSet<Value>)> {
+        if self.quorum == usize::MAX {
+            return Vec::new();
+        }
+        let quorum = self.quorum;
+        let resolved = self.lru_cache.drain_where(|entry| entry.len() >= quorum);
+        resolved
+            .into_iter()
+            .map(|(key, entry)| (key, entry.values))
+            .collect()
+    }
+
+    /// Sets a new value for `quorum`.
+    ///
+    /// This has immediate effect, even for existing key-value entries. Passing `usize::MAX` has
+    /// the same effect as [`disable_quorum()`](#method.disable_quorum).
+    pub fn set_quorum(&mut self, new_size: usize) {
+        self.quorum = new_size;
+    }
+
+    /// Disables quorum resolution entirely: [`add()`](#method.add),
+    /// [`add_with_expiry()`](#method.add_with_expiry) and
+    /// [`add_weighted()`](#method.add_weighted) will always return `None` and
+    /// [`is_quorum_reached()`](#method.is_quorum_reached) will always return `false`, no matter
+    /// how many values accumulate.
+    ///
+    /// Values still accumulate as usual and remain retrievable via
+    /// [`get()`](#method.get); call [`set_quorum()`](#method.set_quorum) with a real threshold to
+    /// re-enable resolution, which takes immediate effect.
+    pub fn disable_quorum(&mut self) {
+        self.quorum = usize::MAX;
+    }
+
+    /// Returns the current value for `quorum`.
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+}
+
+/// A companion to [`Accumulator`](struct.Accumulator.html) that counts quorum over distinct
+/// *sources* rather than distinct *values*: a repeated vote from the same source overwrites its
+/// previous vote rather than inflating the count, so a single contributor can't stuff the
+/// ballot.
+///
+/// Kept as its own type rather than a third type parameter on `Accumulator`, so that
+/// `Accumulator::with_capacity(..)` and friends keep inferring `Key`/`Value` exactly as before
+/// without needing an unrelated `Source` type pinned down at every call site.
+pub struct SourceAccumulator<Key, Source, Value>
+where
+    Key: PartialOrd + Ord + Clone,
+    Source: Clone,
+    Value: Clone,
+{
+    quorum: usize,
+    cache: Backing<Key, HashMap<Source, Value>>,
+}
+
+impl<Key, Source, Value> SourceAccumulator<Key, Source, Value>
+where
+    Key: PartialOrd + Ord + Clone + Hash,
+    Source: Clone + Eq + Hash,
+    Value: Clone + Eq + Hash,
+{
+    /// Constructor for capacity based `SourceAccumulator`.
+    ///
+    /// `quorum` defines the number of distinct sources at and above which
+    /// [`add_with_source()`](#method.add_with_source) will return `Some()`.
+    pub fn with_capacity(quorum: usize, capacity: usize) -> SourceAccumulator<Key, Source, Value> {
+        SourceAccumulator {
+            quorum: quorum,
+            cache: Backing::Lru(LruCache::with_capacity(capacity)),
+        }
+    }
+
+    /// Constructor for time based `SourceAccumulator`.
+    ///
+    /// `quorum` defines the number of distinct sources at and above which
+    /// [`add_with_source()`](#method.add_with_source) will return `Some()`.
+    pub fn with_duration(quorum: usize, duration: Duration) -> SourceAccumulator<Key, Source, Value> {
+        SourceAccumulator {
+            quorum: quorum,
+            cache: Backing::Lru(LruCache::with_expiry_duration(duration)),
+        }
+    }
+
+    /// Returns whether `key` exists in the accumulator or not.
+    pub fn contains_key(&self, key: &Key) -> bool {
+        self.cache.peek(key).is_some()
+    }
+
+    /// Adds a single vote `value` from `source` for `key`.
+    ///
+    /// A repeated call from the same `source` overwrites its previous vote rather than inflating
+    /// the count. Returns the full set of currently voted-for values once `quorum` distinct
+    /// sources have voted for `key`, otherwise returns `None`.
+    pub fn add_with_source(&mut self, key: Key, source: Source, value: Value) -> Option<HashSet<Value>> {
+        let entry = self.cache.entry_or_insert_with(key, HashMap::new);
+        let _ = entry.insert(source, value);
+        if self.quorum != usize::MAX && entry.len() >= self.quorum {
+            Some(entry.values().cloned().collect())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the per-source votes accumulated under `key`, or `None` if `key` doesn't exist.
+    pub fn get_by_source(&self, key: &Key) -> Option<&HashMap<Source, Value>> {
+        self.cache.peek(key)
+    }
+
+    /// Returns the most commonly voted-for value for `key`, provided `quorum` or more distinct
+    /// sources have voted (regardless of whether they agree on the value); otherwise returns
+    /// `None`. Ties are broken arbitrarily.
+    pub fn values_with_quorum(&self, key: &Key) -> Option<Value> {
+        let entry = match self.cache.peek(key) {
+            None => return None,
+            Some(entry) => entry,
+        };
+        if self.quorum == usize::MAX || entry.len() < self.quorum {
+            return None;
+        }
+        let mut counts: HashMap<Value, usize> = HashMap::new();
+        for value in entry.values() {
+            *counts.entry(value.clone()).or_insert(0) += 1;
+        }
+        counts.into_iter().max_by_key(|&(_, count)| count).map(
+            |(value, _)| value,
+        )
+    }
+
+    /// Removes `key` and all corresponding per-source votes.
+    pub fn delete(&mut self, key: &Key) {
+        let _ = self.cache.remove(key);
+    }
+
+    /// Returns the size of the accumulator, i.e. the number of keys held.
+    pub fn cache_size(&mut self) -> usize {
+        self.cache.len()
+    }
+
     /// Sets a new value for `quorum`.
     ///
     /// This has immediate effect, even for existing key-value entries.
@@ -127,6 +503,14 @@ impl<Key: PartialOrd + Ord + Clone, Value: Clone + Eq + Hash> Accumulator<Key, V
         self.quorum = new_size;
     }
 
+    /// Disables quorum resolution entirely: [`add_with_source()`](#method.add_with_source) will
+    /// always return `None`, no matter how many sources vote. Votes still accumulate as usual
+    /// and remain retrievable via [`get_by_source()`](#method.get_by_source); call
+    /// [`set_quorum()`](#method.set_quorum) with a real threshold to re-enable resolution.
+    pub fn disable_quorum(&mut self) {
+        self.quorum = usize::MAX;
+    }
+
     /// Returns the current value for `quorum`.
     pub fn quorum(&self) -> usize {
         self.quorum
@@ -322,4 +706,159 @@ mod test {
         accumulator.set_quorum(random);
         assert_eq!(random, accumulator.quorum());
     }
+
+    #[test]
+    fn add_with_expiry() {
+        use std::thread;
+
+        let mut accumulator = Accumulator::with_capacity(2, 100);
+
+        assert!(
+            accumulator
+                .add_with_expiry(1, 1, Duration::from_millis(50))
+                .is_none()
+        );
+        assert_eq!(accumulator.is_quorum_reached(&1), false);
+
+        // A second, non-expiring vote reaches quorum alongside the still-live expiring one.
+        assert!(accumulator.add(1, 2).is_some());
+        assert_eq!(accumulator.is_quorum_reached(&1), true);
+
+        thread::sleep(Duration::from_millis(100));
+
+        // The expiring vote is still reported as live until the next `add` for this key prunes
+        // it: expiry is only checked on write, not on every read.
+        assert_eq!(accumulator.is_quorum_reached(&1), true);
+
+        // Adding again for the same key prunes the expired vote before counting the new one.
+        assert!(accumulator.add(1, 2).is_none());
+        assert_eq!(accumulator.is_quorum_reached(&1), false);
+        let responses = accumulator.get(&1).expect("entry 1 does not exist");
+        assert_eq!(responses.len(), 1);
+        assert!(responses.contains(&2));
+    }
+
+    #[test]
+    fn disable_quorum() {
+        let mut accumulator = Accumulator::with_capacity(2, 100);
+        accumulator.disable_quorum();
+        assert_eq!(accumulator.quorum(), usize::MAX);
+
+        for value in 0..10 {
+            assert!(accumulator.add(1, value).is_none());
+            assert_eq!(accumulator.is_quorum_reached(&1), false);
+        }
+        let responses = accumulator.get(&1).expect("entry 1 does not exist");
+        assert_eq!(responses.len(), 10);
+
+        // Re-enabling quorum takes immediate effect.
+        accumulator.set_quorum(2);
+        assert_eq!(accumulator.is_quorum_reached(&1), true);
+    }
+
+    #[test]
+    fn source_accumulator_add_with_source() {
+        let mut accumulator: SourceAccumulator<i32, &str, char> = SourceAccumulator::with_capacity(2, 100);
+
+        assert!(accumulator.add_with_source(1, "alice", 'x').is_none());
+        // A repeated vote from the same source overwrites rather than inflating the count.
+        assert!(accumulator.add_with_source(1, "alice", 'y').is_none());
+        assert_eq!(
+            accumulator.get_by_source(&1).expect("entry 1 does not exist").len(),
+            1
+        );
+
+        let responses = accumulator
+            .add_with_source(1, "bob", 'y')
+            .expect("quorum not reached");
+        // Both sources voted 'y', so the set of voted-for values holds that single value.
+        assert_eq!(responses.len(), 1);
+        assert!(responses.contains(&'y'));
+
+        assert_eq!(accumulator.values_with_quorum(&1), Some('y'));
+    }
+
+    #[test]
+    fn with_arc_capacity() {
+        let mut accumulator = Accumulator::with_arc_capacity(1, 100);
+
+        assert!(accumulator.add(2, 3).is_some());
+        assert_eq!(accumulator.contains_key(&2), true);
+        assert_eq!(accumulator.is_quorum_reached(&2), true);
+
+        let responses = accumulator.get(&2).expect("entry 2 does not exist");
+        assert_eq!(responses.len(), 1);
+        assert!(responses.contains(&3));
+
+        accumulator.delete(&2);
+        assert!(accumulator.get(&2).is_none());
+    }
+
+    #[test]
+    fn arc_retains_hot_keys_under_noise() {
+        // A key that is accessed repeatedly (and so gets promoted to T2) should survive a run of
+        // one-off noise keys that would evict it under plain LRU.
+        let mut accumulator = Accumulator::with_arc_capacity(1, 10);
+        let hot_key = 0;
+
+        assert!(accumulator.add(hot_key, 1).is_some());
+        assert!(accumulator.add(hot_key, 2).is_some());
+
+        for noise_key in 1..30 {
+            let _ = accumulator.add(noise_key, 1);
+        }
+
+        assert_eq!(accumulator.contains_key(&hot_key), true);
+    }
+
+    #[test]
+    fn drain_resolved() {
+        let mut accumulator = Accumulator::with_capacity(2, 100);
+
+        assert!(accumulator.add(1, 1).is_none());
+        assert!(accumulator.add(1, 2).is_some());
+        assert!(accumulator.add(2, 1).is_none());
+        assert!(accumulator.add(3, 1).is_none());
+        assert!(accumulator.add(3, 2).is_some());
+
+        let mut resolved = accumulator.drain_resolved();
+        resolved.sort_by_key(|&(key, _)| key);
+
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].0, 1);
+        assert_eq!(resolved[0].1.len(), 2);
+        assert_eq!(resolved[1].0, 3);
+        assert_eq!(resolved[1].1.len(), 2);
+
+        // Resolved keys are gone, the still-accumulating key is untouched.
+        assert!(accumulator.get(&1).is_none());
+        assert!(accumulator.get(&3).is_none());
+        assert_eq!(accumulator.get(&2).expect("entry 2 does not exist").len(), 1);
+
+        // With quorum disabled, nothing is ever considered resolved.
+        accumulator.disable_quorum();
+        assert!(accumulator.add(4, 1).is_none());
+        assert!(accumulator.add(4, 2).is_none());
+        assert!(accumulator.drain_resolved().is_empty());
+    }
+
+    #[test]
+    fn add_weighted() {
+        let mut accumulator: Accumulator<i32, char> = Accumulator::with_capacity(10, 100);
+
+        assert!(accumulator.add_weighted(1, 'x', 3).is_none());
+        assert_eq!(accumulator.accumulated_weight(&1), 3);
+
+        // A lower weight for the same value already voted for doesn't shrink the total.
+        assert!(accumulator.add_weighted(1, 'x', 1).is_none());
+        assert_eq!(accumulator.accumulated_weight(&1), 3);
+
+        let responses = accumulator
+            .add_weighted(1, 'y', 8)
+            .expect("quorum not reached");
+        assert_eq!(accumulator.accumulated_weight(&1), 11);
+        assert_eq!(responses.len(), 2);
+        assert!(responses.contains(&'x'));
+        assert!(responses.contains(&'y'));
+    }
 }